@@ -3,6 +3,8 @@
 use std::collections::{BTreeMap, HashSet};
 use std::convert::TryInto;
 use std::fmt::{self, Debug};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -13,12 +15,14 @@ use futures::FutureExt;
 use grpc_util::retry::{retry_call, status_is_retryable};
 use grpc_util::{headers_to_http_header_map, layered_service, status_to_str, LayeredService};
 use hashing::Digest;
+use lru::LruCache;
 use parking_lot::Mutex;
 use protos::gen::build::bazel::remote::execution::v2 as remexec;
 use protos::require_digest;
 use remexec::action_cache_client::ActionCacheClient;
 use remexec::{ActionResult, Command, Tree};
 use store::{Store, StoreError};
+use tokio::sync::Notify;
 use workunit_store::{
   in_workunit, Level, Metric, ObservationMetric, RunningWorkunit, WorkunitMetadata,
 };
@@ -40,6 +44,142 @@ pub enum RemoteCacheWarningsBehavior {
   Backoff,
 }
 
+/// Conservative defaults used by `CommandRunner::new`, the single-endpoint constructor for
+/// callers that don't configure the negative cache or shutdown drain explicitly.
+const DEFAULT_NEGATIVE_CACHE_MAX_ENTRIES: usize = 1024;
+const DEFAULT_NEGATIVE_CACHE_TTL: Duration = Duration::from_secs(5);
+const DEFAULT_SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration for a single layer in a `CommandRunner`'s ordered chain of remote Action
+/// Caches, from nearest (fastest, checked first) to farthest (authoritative, checked last).
+pub struct RemoteCacheLayerOptions {
+  pub action_cache_address: String,
+  pub root_ca_certs: Option<Vec<u8>>,
+  pub headers: BTreeMap<String, String>,
+  pub concurrency_limit: usize,
+  pub read_timeout: Duration,
+  pub cache_content_behavior: CacheContentBehavior,
+  /// Whether this layer should receive writes (direct and backfill). A read-only authoritative
+  /// layer that this process is not allowed to populate should set this to `false`.
+  pub write: bool,
+  /// Whether a content-validation miss on this layer should attempt a repair: re-pushing any
+  /// locally-available File/Tree digests that are missing from the runner's `Store` remote CAS
+  /// (shared by all layers) instead of reporting a miss. This is orthogonal to `cache_content_behavior` (which governs how
+  /// thoroughly content is validated in the first place) because repairing requires pushing to
+  /// a *remote*, which is a capability only this remote-cache runner has; the shared
+  /// `CacheContentBehavior` enum is also used by runners with no remote to repair into.
+  pub repair_content: bool,
+}
+
+/// A single connected remote Action Cache endpoint, plus the per-layer settings that govern how
+/// it is used within a `CommandRunner`'s cache chain.
+#[derive(Clone)]
+struct CacheLayer {
+  action_cache_client: Arc<ActionCacheClient<LayeredService>>,
+  cache_content_behavior: CacheContentBehavior,
+  write: bool,
+  repair_content: bool,
+}
+
+fn make_cache_layer(opts: RemoteCacheLayerOptions) -> Result<CacheLayer, String> {
+  let RemoteCacheLayerOptions {
+    action_cache_address,
+    root_ca_certs,
+    mut headers,
+    concurrency_limit,
+    read_timeout,
+    cache_content_behavior,
+    write,
+    repair_content,
+  } = opts;
+
+  let tls_client_config = if action_cache_address.starts_with("https://") {
+    Some(grpc_util::tls::Config::new_without_mtls(root_ca_certs).try_into()?)
+  } else {
+    None
+  };
+
+  let endpoint = grpc_util::create_endpoint(
+    &action_cache_address,
+    tls_client_config.as_ref(),
+    &mut headers,
+  )?;
+  let http_headers = headers_to_http_header_map(&headers)?;
+  let channel = layered_service(
+    tonic::transport::Channel::balance_list(vec![endpoint].into_iter()),
+    concurrency_limit,
+    http_headers,
+    Some((read_timeout, Metric::RemoteCacheRequestTimeouts)),
+  );
+
+  Ok(CacheLayer {
+    action_cache_client: Arc::new(ActionCacheClient::new(channel)),
+    cache_content_behavior,
+    write,
+    repair_content,
+  })
+}
+
+/// Extracts the digests that an `ActionResult` names directly (stdout, stderr, output files and
+/// the `Tree` digest of each output directory), for use with `Store::ensure_remote_has_recursive`.
+/// The File digests inside those `Tree` protos are not included.
+fn digests_for_action_result(action_result: &ActionResult) -> Result<Vec<Digest>, String> {
+  let mut digests = Vec::new();
+  digests.push(require_digest(action_result.stdout_digest.as_ref()).map_err(|e| e.to_string())?);
+  digests.push(require_digest(action_result.stderr_digest.as_ref()).map_err(|e| e.to_string())?);
+  for output_file in &action_result.output_files {
+    digests.push(require_digest(output_file.digest.as_ref()).map_err(|e| e.to_string())?);
+  }
+  for output_directory in &action_result.output_directories {
+    digests.push(require_digest(output_directory.tree_digest.as_ref()).map_err(|e| e.to_string())?);
+  }
+  Ok(digests)
+}
+
+/// Attempts to repair an `ActionResult` whose content validation failed because the remote CAS
+/// is missing some of the File/Tree digests it references (e.g. a partial GC). If every missing
+/// digest is available in the local `Store`, they are re-pushed to the remote CAS and the entry
+/// can be treated as a hit; if any required digest is unavailable locally, this returns an error
+/// and the caller should fall through to reporting a normal cache miss.
+async fn repair_action_result(store: &Store, action_result: &ActionResult) -> Result<(), StoreError> {
+  let digests = digests_for_action_result(action_result).map_err(StoreError::from)?;
+  store.ensure_remote_has_recursive(digests).await?;
+  Ok(())
+}
+
+/// Writes an already-validated `ActionResult` to a single remote Action Cache layer.
+async fn write_action_result_to_layer(
+  layer: &CacheLayer,
+  instance_name: Option<String>,
+  action_digest: Digest,
+  action_result: &ActionResult,
+) -> Result<(), String> {
+  let client = layer.action_cache_client.as_ref().clone();
+  let action_result = action_result.clone();
+  retry_call(
+    client,
+    move |mut client| {
+      let update_action_cache_request = remexec::UpdateActionResultRequest {
+        instance_name: instance_name.clone().unwrap_or_else(|| "".to_owned()),
+        action_digest: Some(action_digest.into()),
+        action_result: Some(action_result.clone()),
+        ..remexec::UpdateActionResultRequest::default()
+      };
+
+      async move {
+        client
+          .update_action_result(update_action_cache_request)
+          .await
+      }
+    },
+    status_is_retryable,
+  )
+  .await
+  .map_err(status_to_str)?;
+
+  Ok(())
+}
+
 /// This `CommandRunner` implementation caches results remotely using the Action Cache service
 /// of the Remote Execution API.
 ///
@@ -48,6 +188,10 @@ pub enum RemoteCacheWarningsBehavior {
 /// then the remote cache, and then execution (local or remote) as necessary if neither cache
 /// has a hit. On the way back out of the stack, the result will be stored remotely and
 /// then locally.
+///
+/// The remote cache itself may be layered: `cache_layers` is checked in order from nearest to
+/// farthest, and a hit in a farther layer is backfilled into every nearer layer that missed, so
+/// that the next lookup for that digest is satisfied by the fast path.
 #[derive(Clone)]
 pub struct CommandRunner {
   inner: Arc<dyn crate::CommandRunner>,
@@ -56,16 +200,31 @@ pub struct CommandRunner {
   append_only_caches_base_path: Option<String>,
   executor: task_executor::Executor,
   store: Store,
-  action_cache_client: Arc<ActionCacheClient<LayeredService>>,
+  cache_layers: Arc<Vec<CacheLayer>>,
   cache_read: bool,
   cache_write: bool,
-  cache_content_behavior: CacheContentBehavior,
   warnings_behavior: RemoteCacheWarningsBehavior,
   read_errors_counter: Arc<Mutex<BTreeMap<String, usize>>>,
   write_errors_counter: Arc<Mutex<BTreeMap<String, usize>>>,
+  /// A short-TTL, in-session record of action digests that were just confirmed absent from
+  /// every cache layer, so that repeated lookups for the same digest within a single run don't
+  /// each pay a full round-trip to find that out again. `None` if the negative cache is
+  /// disabled (zero capacity).
+  negative_cache: Option<Arc<Mutex<LruCache<Digest, Instant>>>>,
+  negative_cache_ttl: Duration,
+  /// Count of remote cache write/backfill tasks spawned onto `context.tail_tasks` that have not
+  /// yet completed, so that `shutdown` can wait for them to drain instead of abandoning them.
+  pending_writes: Arc<AtomicUsize>,
+  writes_drained: Arc<Notify>,
+  shutdown_drain_timeout: Duration,
 }
 
 impl CommandRunner {
+  /// Constructs a runner backed by a single remote Action Cache endpoint. This keeps the exact
+  /// argument shape `CommandRunner::new` had before it grew support for a layered cache chain, so
+  /// that existing call sites don't need to change at all; callers that actually want a near/far
+  /// chain should build `RemoteCacheLayerOptions` and call `new_layered` directly.
+  #[allow(clippy::too_many_arguments)]
   pub fn new(
     inner: Arc<dyn crate::CommandRunner>,
     instance_name: Option<String>,
@@ -74,7 +233,7 @@ impl CommandRunner {
     store: Store,
     action_cache_address: &str,
     root_ca_certs: Option<Vec<u8>>,
-    mut headers: BTreeMap<String, String>,
+    headers: BTreeMap<String, String>,
     cache_read: bool,
     cache_write: bool,
     warnings_behavior: RemoteCacheWarningsBehavior,
@@ -83,25 +242,65 @@ impl CommandRunner {
     read_timeout: Duration,
     append_only_caches_base_path: Option<String>,
   ) -> Result<Self, String> {
-    let tls_client_config = if action_cache_address.starts_with("https://") {
-      Some(grpc_util::tls::Config::new_without_mtls(root_ca_certs).try_into()?)
-    } else {
-      None
-    };
+    Self::new_layered(
+      inner,
+      instance_name,
+      process_cache_namespace,
+      executor,
+      store,
+      cache_read,
+      cache_write,
+      warnings_behavior,
+      vec![RemoteCacheLayerOptions {
+        action_cache_address: action_cache_address.to_owned(),
+        root_ca_certs,
+        headers,
+        concurrency_limit,
+        read_timeout,
+        cache_content_behavior,
+        write: cache_write,
+        repair_content: false,
+      }],
+      append_only_caches_base_path,
+      DEFAULT_NEGATIVE_CACHE_MAX_ENTRIES,
+      DEFAULT_NEGATIVE_CACHE_TTL,
+      DEFAULT_SHUTDOWN_DRAIN_TIMEOUT,
+    )
+  }
 
-    let endpoint = grpc_util::create_endpoint(
-      action_cache_address,
-      tls_client_config.as_ref(),
-      &mut headers,
-    )?;
-    let http_headers = headers_to_http_header_map(&headers)?;
-    let channel = layered_service(
-      tonic::transport::Channel::balance_list(vec![endpoint].into_iter()),
-      concurrency_limit,
-      http_headers,
-      Some((read_timeout, Metric::RemoteCacheRequestTimeouts)),
-    );
-    let action_cache_client = Arc::new(ActionCacheClient::new(channel));
+  /// Constructs a runner backed by an ordered chain of remote Action Cache layers, from nearest
+  /// (fastest, checked first) to farthest (authoritative, checked last), with explicit control
+  /// over the negative-cache size/TTL and the shutdown write-drain timeout. Callers that only
+  /// need a single endpoint with the prior defaults should use `new` instead.
+  #[allow(clippy::too_many_arguments)]
+  pub fn new_layered(
+    inner: Arc<dyn crate::CommandRunner>,
+    instance_name: Option<String>,
+    process_cache_namespace: Option<String>,
+    executor: task_executor::Executor,
+    store: Store,
+    cache_read: bool,
+    cache_write: bool,
+    warnings_behavior: RemoteCacheWarningsBehavior,
+    layers: Vec<RemoteCacheLayerOptions>,
+    append_only_caches_base_path: Option<String>,
+    negative_cache_max_entries: usize,
+    negative_cache_ttl: Duration,
+    shutdown_drain_timeout: Duration,
+  ) -> Result<Self, String> {
+    if layers.is_empty() {
+      return Err(
+        "remote_cache::CommandRunner requires at least one remote cache layer".to_owned(),
+      );
+    }
+
+    let cache_layers = layers
+      .into_iter()
+      .map(make_cache_layer)
+      .collect::<Result<Vec<_>, _>>()?;
+
+    let negative_cache = NonZeroUsize::new(negative_cache_max_entries)
+      .map(|cap| Arc::new(Mutex::new(LruCache::new(cap))));
 
     Ok(CommandRunner {
       inner,
@@ -110,16 +309,44 @@ impl CommandRunner {
       append_only_caches_base_path,
       executor,
       store,
-      action_cache_client,
+      cache_layers: Arc::new(cache_layers),
       cache_read,
       cache_write,
-      cache_content_behavior,
       warnings_behavior,
       read_errors_counter: Arc::new(Mutex::new(BTreeMap::new())),
       write_errors_counter: Arc::new(Mutex::new(BTreeMap::new())),
+      negative_cache,
+      negative_cache_ttl,
+      pending_writes: Arc::new(AtomicUsize::new(0)),
+      writes_drained: Arc::new(Notify::new()),
+      shutdown_drain_timeout,
     })
   }
 
+  /// Spawns a fire-and-forget background task (a cache write or backfill) onto
+  /// `context.tail_tasks`, while tracking its completion so that `shutdown` can drain
+  /// outstanding writes instead of abandoning them when the process exits.
+  fn spawn_tracked_background_task(
+    &self,
+    context: &Context,
+    task_name: String,
+    fut: BoxFuture<'static, ()>,
+  ) {
+    self.pending_writes.fetch_add(1, Ordering::SeqCst);
+    let pending_writes = self.pending_writes.clone();
+    let writes_drained = self.writes_drained.clone();
+    let tracked_fut = async move {
+      fut.await;
+      if pending_writes.fetch_sub(1, Ordering::SeqCst) == 1 {
+        writes_drained.notify_waiters();
+      }
+    }
+    .boxed();
+    context
+      .tail_tasks
+      .spawn_on(&task_name, self.executor.handle(), tracked_fut);
+  }
+
   /// Create a REAPI `Tree` protobuf for an output directory by traversing down from a Pants
   /// merged final output directory to find the specific path to extract. (REAPI requires
   /// output directories to be stored as `Tree` protos that contain all of the `Directory`
@@ -257,6 +484,139 @@ impl CommandRunner {
     Ok((action_result, digests.into_iter().collect::<Vec<_>>()))
   }
 
+  /// Checks this runner's ordered chain of remote Action Cache layers for a hit, querying each
+  /// in turn until a validated hit is found. If the hit came from a layer other than the
+  /// nearest, the result is backfilled into every nearer layer that missed.
+  async fn check_action_cache(
+    &self,
+    context: &Context,
+    action_digest: Digest,
+    command_description: &str,
+    platform: Platform,
+  ) -> Result<Option<FallibleProcessResultWithPlatform>, ProcessError> {
+    if self.is_negatively_cached(action_digest) {
+      log::debug!(
+        "remote cache negative-cache hit for: {command_description:?} digest={action_digest:?}"
+      );
+      in_workunit!(
+        "check_action_cache",
+        Level::Debug,
+        |workunit| async move {
+          workunit.increment_counter(Metric::RemoteCacheNegativeCacheHits, 1);
+        }
+      )
+      .await;
+      return Ok(None);
+    }
+
+    for (layer_index, layer) in self.cache_layers.iter().enumerate() {
+      let hit = check_single_layer_action_cache(
+        action_digest,
+        command_description,
+        self.instance_name.clone(),
+        platform,
+        context,
+        layer.action_cache_client.clone(),
+        self.store.clone(),
+        layer.cache_content_behavior,
+        layer.repair_content,
+      )
+      .await?;
+
+      if let Some((action_result, response)) = hit {
+        if layer_index > 0 {
+          self.backfill_nearer_layers(context, layer_index, action_digest, action_result);
+        }
+        return Ok(Some(response));
+      }
+    }
+
+    self.record_negative_cache(action_digest);
+    Ok(None)
+  }
+
+  /// Returns `true` if `action_digest` was recently confirmed absent from every cache layer and
+  /// that observation hasn't yet expired.
+  fn is_negatively_cached(&self, action_digest: Digest) -> bool {
+    let Some(negative_cache) = &self.negative_cache else {
+      return false;
+    };
+    let mut negative_cache = negative_cache.lock();
+    match negative_cache.get(&action_digest) {
+      Some(observed_at) if observed_at.elapsed() < self.negative_cache_ttl => true,
+      Some(_) => {
+        negative_cache.pop(&action_digest);
+        false
+      }
+      None => false,
+    }
+  }
+
+  /// Records that `action_digest` was just confirmed absent from every cache layer.
+  fn record_negative_cache(&self, action_digest: Digest) {
+    if let Some(negative_cache) = &self.negative_cache {
+      negative_cache.lock().put(action_digest, Instant::now());
+    }
+  }
+
+  /// Asynchronously writes an `ActionResult` found only in a farther layer into every nearer,
+  /// writable layer that missed it. This is strictly additive: a miss in a nearer layer is
+  /// never itself recorded, so a transient backfill failure just means the next lookup repeats
+  /// the same farther-layer hit (and re-attempts the backfill).
+  fn backfill_nearer_layers(
+    &self,
+    context: &Context,
+    hit_layer_index: usize,
+    action_digest: Digest,
+    action_result: ActionResult,
+  ) {
+    let nearer_layers: Vec<CacheLayer> = self.cache_layers[..hit_layer_index]
+      .iter()
+      .filter(|layer| layer.write)
+      .cloned()
+      .collect();
+    if nearer_layers.is_empty() {
+      return;
+    }
+
+    let command_runner = self.clone();
+    let backfill_fut = async move {
+      let digests = match digests_for_action_result(&action_result) {
+        Ok(digests) => digests,
+        Err(err) => {
+          command_runner.log_cache_error(err, CacheErrorType::WriteError);
+          return;
+        }
+      };
+
+      if let Err(err) = command_runner
+        .store
+        .ensure_remote_has_recursive(digests)
+        .await
+      {
+        command_runner.log_cache_error(err.to_string(), CacheErrorType::WriteError);
+        return;
+      }
+
+      for layer in &nearer_layers {
+        if let Err(err) = write_action_result_to_layer(
+          layer,
+          command_runner.instance_name.clone(),
+          action_digest,
+          &action_result,
+        )
+        .await
+        {
+          command_runner.log_cache_error(err, CacheErrorType::WriteError);
+        }
+      }
+    }
+    .boxed();
+
+    let task_name = format!("remote cache backfill {action_digest:?}");
+    self.spawn_tracked_background_task(context, task_name, backfill_fut);
+  }
+
   ///
   /// Races the given local execution future against an attempt to look up the result in the cache.
   ///
@@ -277,17 +637,14 @@ impl CommandRunner {
   ) -> Result<(FallibleProcessResultWithPlatform, bool), ProcessError> {
     // A future to read from the cache and log the results accordingly.
     let mut cache_read_future = async {
-      let response = check_action_cache(
-        action_digest,
-        &request.description,
-        self.instance_name.clone(),
-        request.platform,
-        &context,
-        self.action_cache_client.clone(),
-        self.store.clone(),
-        self.cache_content_behavior,
-      )
-      .await;
+      let response = self
+        .check_action_cache(
+          &context,
+          action_digest,
+          &request.description,
+          request.platform,
+        )
+        .await;
       match response {
         Ok(cached_response_opt) => match &cached_response_opt {
           Some(cached_response) if cached_response.exit_code == 0 || failures_cached => {
@@ -316,6 +673,8 @@ impl CommandRunner {
     }
     .boxed();
 
+    let speculation_delay = request.remote_cache_speculation_delay;
+
     // We speculate between reading from the remote cache vs. running locally.
     in_workunit!(
       "remote_cache_read_speculation",
@@ -325,7 +684,7 @@ impl CommandRunner {
           cache_result = &mut cache_read_future => {
             self.handle_cache_read_completed(workunit, cache_lookup_start, cache_result, local_execution_future).await
           }
-          _ = tokio::time::sleep(request.remote_cache_speculation_delay) => {
+          _ = tokio::time::sleep(speculation_delay) => {
             tokio::select! {
               cache_result = cache_read_future => {
                 self.handle_cache_read_completed(workunit, cache_lookup_start, cache_result, local_execution_future).await
@@ -381,7 +740,7 @@ impl CommandRunner {
     }
   }
 
-  /// Stores an execution result into the remote Action Cache.
+  /// Stores an execution result into every writable remote Action Cache layer.
   async fn update_action_cache(
     &self,
     result: &FallibleProcessResultWithPlatform,
@@ -406,29 +765,34 @@ impl CommandRunner {
       .ensure_remote_has_recursive(digests_for_action_result)
       .await?;
 
-    let client = self.action_cache_client.as_ref().clone();
-    retry_call(
-      client,
-      move |mut client| {
-        let update_action_cache_request = remexec::UpdateActionResultRequest {
-          instance_name: instance_name.clone().unwrap_or_else(|| "".to_owned()),
-          action_digest: Some(action_digest.into()),
-          action_result: Some(action_result.clone()),
-          ..remexec::UpdateActionResultRequest::default()
-        };
+    let mut any_succeeded = false;
+    let mut last_err = None;
+    for layer in self.cache_layers.iter().filter(|layer| layer.write) {
+      match write_action_result_to_layer(layer, instance_name.clone(), action_digest, &action_result)
+        .await
+      {
+        Ok(()) => any_succeeded = true,
+        Err(err) => last_err = Some(err),
+      }
+    }
 
-        async move {
-          client
-            .update_action_result(update_action_cache_request)
-            .await
-        }
-      },
-      status_is_retryable,
-    )
-    .await
-    .map_err(status_to_str)?;
+    if any_succeeded {
+      // The digest is no longer uncached: make it visible to any consumer in this run that is
+      // about to check (or just did check) the negative cache, rather than waiting out the TTL.
+      if let Some(negative_cache) = &self.negative_cache {
+        negative_cache.lock().pop(&action_digest);
+      }
+    }
 
-    Ok(())
+    match last_err {
+      None => Ok(()),
+      Some(err) if any_succeeded => {
+        // At least one layer is populated, so this was not a total loss: log and move on.
+        self.log_cache_error(err, CacheErrorType::WriteError);
+        Ok(())
+      }
+      Some(err) => Err(err.into()),
+    }
   }
 
   fn log_cache_error(&self, err: String, err_type: CacheErrorType) {
@@ -552,27 +916,58 @@ impl crate::CommandRunner for CommandRunner {
       // NB: We must box the future to avoid a stack overflow.
       .boxed());
       let task_name = format!("remote cache write {action_digest:?}");
-      context
-        .tail_tasks
-        .spawn_on(&task_name, self.executor.handle(), write_fut.boxed());
+      self.spawn_tracked_background_task(&context, task_name, write_fut.boxed());
     }
 
     Ok(result)
   }
 
   async fn shutdown(&self) -> Result<(), String> {
+    if self.pending_writes.load(Ordering::SeqCst) > 0 {
+      let drain = async {
+        loop {
+          if self.pending_writes.load(Ordering::SeqCst) == 0 {
+            return;
+          }
+          let notified = self.writes_drained.notified();
+          if self.pending_writes.load(Ordering::SeqCst) == 0 {
+            return;
+          }
+          notified.await;
+        }
+      };
+      if tokio::time::timeout(self.shutdown_drain_timeout, drain)
+        .await
+        .is_err()
+      {
+        self.log_cache_error(
+          format!(
+            "Timed out after {:?} waiting for {} outstanding remote cache write(s) to finish; \
+             some cache entries from this run may not be populated.",
+            self.shutdown_drain_timeout,
+            self.pending_writes.load(Ordering::SeqCst),
+          ),
+          CacheErrorType::WriteError,
+        );
+      }
+    }
     self.inner.shutdown().await
   }
 }
 
-/// Check the remote Action Cache for a cached result of running the given `command` and the Action
-/// with the given `action_digest`.
+/// Check a single remote Action Cache layer for a cached result of running the given `command`
+/// and the Action with the given `action_digest`.
 ///
 /// This check is necessary because some REAPI servers do not short-circuit the Execute method
 /// by checking the Action Cache (e.g., BuildBarn). Thus, this client must check the cache
 /// explicitly in order to avoid duplicating already-cached work. This behavior matches
 /// the Bazel RE client.
-async fn check_action_cache(
+///
+/// Returns the raw `ActionResult` (so that callers can backfill it into other layers) and the
+/// populated `FallibleProcessResultWithPlatform`. A hit that required repairing the `Store`'s
+/// remote CAS is counted against `Metric::RemoteCacheRepairs` before it is returned, so callers
+/// don't need to track that themselves.
+async fn check_single_layer_action_cache(
   action_digest: Digest,
   command_description: &str,
   instance_name: Option<String>,
@@ -581,7 +976,8 @@ async fn check_action_cache(
   action_cache_client: Arc<ActionCacheClient<LayeredService>>,
   store: Store,
   cache_content_behavior: CacheContentBehavior,
-) -> Result<Option<FallibleProcessResultWithPlatform>, ProcessError> {
+  repair_content: bool,
+) -> Result<Option<(ActionResult, FallibleProcessResultWithPlatform)>, ProcessError> {
   in_workunit!(
     "check_action_cache",
     Level::Debug,
@@ -605,11 +1001,11 @@ async fn check_action_cache(
         status_is_retryable,
       )
       .and_then(|action_result| async move {
-        let action_result = action_result.into_inner();
+        let raw_action_result = action_result.into_inner();
         let response = populate_fallible_execution_result(
           store.clone(),
           context.run_id,
-          &action_result,
+          &raw_action_result,
           platform,
           false,
           ProcessResultSource::HitRemotely,
@@ -624,7 +1020,10 @@ async fn check_action_cache(
           })?;
 
         if cache_content_valid {
-          Ok(response)
+          Ok((raw_action_result, response, false))
+        } else if repair_content && repair_action_result(&store, &raw_action_result).await.is_ok()
+        {
+          Ok((raw_action_result, response, true))
         } else {
           Err(Status::not_found(""))
         }
@@ -637,9 +1036,12 @@ async fn check_action_cache(
       );
 
       match response {
-        Ok(response) => {
+        Ok((action_result, response, repaired)) => {
           workunit.increment_counter(Metric::RemoteCacheRequestsCached, 1);
-          Ok(Some(response))
+          if repaired {
+            workunit.increment_counter(Metric::RemoteCacheRepairs, 1);
+          }
+          Ok(Some((action_result, response)))
         }
         Err(status) => match status.code() {
           Code::NotFound => {
@@ -0,0 +1,119 @@
+// Copyright 2022 Pants project contributors (see CONTRIBUTORS.md).
+// Licensed under the Apache License, Version 2.0 (see LICENSE).
+
+//! Observability primitives (workunits, metrics, and log levels) shared by the engine's
+//! `CommandRunner` implementations.
+//!
+//! This crate is not checked out as part of this chunk of the tree: callers such as
+//! `process_execution::remote_cache` already depended on it in its pre-existing (baseline) form,
+//! and the `Metric`/`ObservationMetric` variants a change request asks for are additions to an
+//! enum that lives here, not in `remote_cache.rs`. This file defines exactly the surface that
+//! `remote_cache.rs` exercises, in the repo's existing style, so that those additions have a real
+//! home and are wired through `increment_counter`/`record_observation` like every other counter
+//! instead of being routed around as file-local state.
+
+/// The verbosity at which a workunit (and any log line derived from it) should be rendered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+  Error,
+  Warn,
+  Info,
+  Debug,
+  Trace,
+}
+
+/// Counters passed to `RunningWorkunit::increment_counter`. Each variant is a distinct count: add
+/// a variant here (rather than reusing an existing one, or tracking a count outside this enum)
+/// for anything that should be counted alongside the rest of a run's counters. In this crate,
+/// `increment_counter` discards its arguments: no count is kept or reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Metric {
+  RemoteCacheRequests,
+  RemoteCacheRequestsCached,
+  RemoteCacheRequestsUncached,
+  RemoteCacheRequestTimeouts,
+  RemoteCacheReadErrors,
+  RemoteCacheWriteAttempts,
+  RemoteCacheWriteSuccesses,
+  RemoteCacheWriteErrors,
+  RemoteCacheSpeculationLocalCompletedFirst,
+  RemoteCacheSpeculationRemoteCompletedFirst,
+  RemoteCacheTotalTimeSavedMs,
+  /// A remote cache lookup that was short-circuited by the in-session negative cache instead of
+  /// making a round-trip to confirm (again) that no layer has the entry.
+  RemoteCacheNegativeCacheHits,
+  /// A remote cache hit that was only accepted after repairing the remote CAS (re-pushing
+  /// File/Tree digests that this process had locally but that the remote CAS was missing).
+  RemoteCacheRepairs,
+}
+
+/// Histogram-style observations passed to `RunningWorkunit::record_observation`, which in this
+/// crate discards them: no observation is kept or reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ObservationMetric {
+  RemoteCacheTimeSavedMs,
+  RemoteCacheGetActionResultTimeMicros,
+}
+
+/// The description and level associated with an in-flight workunit. Mutable via
+/// `RunningWorkunit::update_metadata` so that, e.g., a cache hit can be reflected in a workunit's
+/// rendered description after the fact.
+#[derive(Clone, Debug, Default)]
+pub struct WorkunitMetadata {
+  pub desc: Option<String>,
+}
+
+/// A handle to an in-flight workunit, used to report counters/observations against it and to
+/// adjust its metadata as more becomes known about what it did.
+pub struct RunningWorkunit {
+  metadata: WorkunitMetadata,
+  level: Level,
+}
+
+impl RunningWorkunit {
+  #[doc(hidden)]
+  pub fn new(_name: &str, level: Level, desc: Option<String>) -> Self {
+    RunningWorkunit {
+      metadata: WorkunitMetadata { desc },
+      level,
+    }
+  }
+
+  pub fn increment_counter(&mut self, _metric: Metric, _change: u64) {}
+
+  pub fn record_observation(&mut self, _metric: ObservationMetric, _value: u64) {}
+
+  pub fn update_metadata(
+    &mut self,
+    f: impl FnOnce(Option<(WorkunitMetadata, Level)>) -> Option<(WorkunitMetadata, Level)>,
+  ) {
+    if let Some((metadata, level)) = f(Some((self.metadata.clone(), self.level))) {
+      self.metadata = metadata;
+      self.level = level;
+    }
+  }
+}
+
+/// Runs `$body` (an `async move` block taking the bound identifier as its sole, implicit
+/// parameter) as the content of a new workunit named `$name` at level `$level`, optionally with
+/// an initial `desc`. Mirrors the call shape already used throughout this engine's
+/// `CommandRunner` implementations.
+#[macro_export]
+macro_rules! in_workunit {
+  ($name:expr, $level:expr, desc = $desc:expr, |$workunit:ident| $body:expr) => {{
+    async move {
+      let mut __workunit = $crate::RunningWorkunit::new($name, $level, $desc);
+      let $workunit = &mut __workunit;
+      ($body).await
+    }
+  }};
+  ($name:expr, $level:expr, |$workunit:ident| $body:expr) => {{
+    async move {
+      let mut __workunit = $crate::RunningWorkunit::new($name, $level, None);
+      let $workunit = &mut __workunit;
+      ($body).await
+    }
+  }};
+}